@@ -0,0 +1,59 @@
+/* atom_feed.rs
+    収集した CommitInfo を Atom フィード (atom_syndication) として書き出すモジュール
+    実装の背景:
+    - 絵文字付き println! は CI ログ向けで、「監視対象ファイルが変わったら RSS/Atom
+      リーダーに通知を出したい」というユースケース (Dockerfile や ci.yml の変更監視) には向かない
+    - github-label-feed の issue フィードと同じ発想で、ファイル単位のコミット変更を
+      Atom の <entry> にマッピングし、ファイルへ書き出す
+*/
+
+use anyhow::{Context, Result};
+use atom_syndication::{Entry, EntryBuilder, Feed, FeedBuilder, LinkBuilder, PersonBuilder};
+use chrono::{DateTime, FixedOffset, Utc};
+
+use crate::CommitInfo;
+
+/// `CommitInfo` 1 件を Atom の `<entry>` に変換
+/// - title: "{repo_full}/{path}"、link: リポジトリ URL、author: login
+/// - id/updated: sha + committed_date から一意な ID と更新日時を組み立てる
+fn entry_from_commit(info: &CommitInfo) -> Entry {
+    let updated: DateTime<FixedOffset> = DateTime::parse_from_rfc3339(&info.date)
+        .unwrap_or_else(|_| Utc::now().into());
+
+    let link = LinkBuilder::default().href(info.url.clone()).build();
+    let author = PersonBuilder::default().name(info.login.clone()).build();
+
+    EntryBuilder::default()
+        .title(format!("{}/{}", info.repo_full, info.path))
+        .id(format!("{}#{}", info.url, info.sha))
+        .updated(updated)
+        .links(vec![link])
+        .authors(vec![author])
+        .build()
+}
+
+/// 収集した `CommitInfo` を 1 本の Atom フィードにまとめる
+pub fn build_feed(filename: &str, site_url: &str, history: &[CommitInfo]) -> Feed {
+    let entries: Vec<Entry> = history.iter().map(entry_from_commit).collect();
+    let updated = entries
+        .iter()
+        .map(|e| *e.updated())
+        .max()
+        .unwrap_or_else(|| Utc::now().into());
+
+    FeedBuilder::default()
+        .title(format!("git-searcher: {}", filename))
+        .id(site_url.to_string())
+        .updated(updated)
+        .entries(entries)
+        .build()
+}
+
+/// 組み立てたフィードをファイルに書き出す (RSS/Atom リーダーがここを購読する)
+pub fn write_feed(feed: &Feed, path: &str) -> Result<()> {
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("Atom フィードの書き出し先を作成できません: {}", path))?;
+    feed.write_to(file)
+        .context("Atom フィードのシリアライズに失敗")?;
+    Ok(())
+}
@@ -0,0 +1,63 @@
+/* bin/fetch-schema.rs
+    接続先の GHES/GitHub.com の GraphQL スキーマを introspection クエリで取得し、
+    `src/schema.json` に書き出すビルドヘルパー
+    実装の背景:
+    - これまで `FileBlame`/`RepoSearch` の derive は手書きの `src/dummy.graphql` を
+      スキーマとして参照していたため、GHES のバージョン差異 (例:
+      `defaultBranchRef.target` の union 構成の違い) がビルド時に検出できず、
+      実行時に初めて壊れることがあった
+    - `graphql-client introspect-schema` と同じ introspection クエリを実機に投げ、
+      実際のスキーマを `schema.json` として固定することでコンパイル時にフィールド/
+      enum のミスマッチを検出できるようにする
+    - 最初これは本体バイナリの `fetch-schema` サブコマンドだったが、`query.rs` の
+      `derive(GraphQLQuery)` が `schema_path = "src/schema.json"` をコンパイル時に
+      要求するため、本体バイナリをビルドしないと `schema.json` を作れず、
+      `schema.json` が無いと本体バイナリがビルドできないという堂々巡りになっていた。
+      `query` モジュールに依存しない独立した `src/bin/` バイナリに分離し、
+      こちらだけを単独でビルド・実行できるようにして依存を断ち切る
+*/
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::env;
+
+/// 標準の GraphQL introspection クエリ (`graphql-client introspect-schema` と同じもの)
+const INTROSPECTION_QUERY: &str = include_str!("../introspection.graphql");
+
+/// `derive(GraphQLQuery)` の `schema_path` が指すスキーマファイルの書き出し先
+const SCHEMA_PATH: &str = "src/schema.json";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let ghe_url = env::var("GHE_URL").context("環境変数 GHE_URL が設定されていません")?;
+    let token = env::var("GITHUB_TOKEN").context("環境変数 GITHUB_TOKEN が設定されていません")?;
+    let graphql_url = format!("{}/api/graphql", ghe_url.trim_end_matches('/'));
+
+    let client = Client::new();
+    let res = client
+        .post(&graphql_url)
+        .bearer_auth(&token)
+        .json(&json!({ "query": INTROSPECTION_QUERY }))
+        .send()
+        .await
+        .with_context(|| format!("introspection クエリの送信に失敗: {}", graphql_url))?;
+
+    let body: Value = res
+        .error_for_status()
+        .with_context(|| format!("introspection クエリが失敗しました: {}", graphql_url))?
+        .json()
+        .await
+        .context("introspection レスポンスの JSON パースに失敗")?;
+
+    if let Some(errors) = body.get("errors") {
+        anyhow::bail!("introspection クエリがエラーを返しました: {}", errors);
+    }
+
+    let pretty = serde_json::to_string_pretty(&body).context("スキーマの整形に失敗")?;
+    std::fs::write(SCHEMA_PATH, pretty)
+        .with_context(|| format!("{} への書き出しに失敗", SCHEMA_PATH))?;
+
+    println!("✅ スキーマを書き出しました: {}", SCHEMA_PATH);
+    Ok(())
+}
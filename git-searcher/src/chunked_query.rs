@@ -0,0 +1,320 @@
+/* chunked_query.rs
+    GraphQL のカーソルページネーションを持つコネクション型クエリを汎用化するためのモジュール
+    実装の背景:
+    - `history(path: $path, first: 1)` のように first:1 決め打ちだと最新の 1 件しか
+      取れず、`edges.first()` を直接アンラップするコードがクエリごとに重複しがちだった
+    - `ChunkedQuery` を実装したクエリなら `run_chunked` に渡すだけで
+      `hasNextPage` が尽きるまでページを辿り、`Item` を 1 本の Vec に連結できる
+*/
+
+use anyhow::{Context, Result};
+use graphql_client::{GraphQLQuery, Response};
+use reqwest::Client;
+
+/// コネクション型 (edges/pageInfo) を持つ GraphQL クエリを汎用的にページングするためのトレイト
+/// - `Item`: 1 ページ分から取り出す要素の型
+/// - `change_after`: 次ページ取得用に `after` 変数を書き換える
+/// - `set_batch`: 1 ページあたりの取得件数 (`first`) を設定する
+/// - `process`: レスポンスから `Item` の一覧と次カーソル (`None` なら最終ページ) を取り出す
+/// - `item_sha`: `run_chunked_until` が「前回確認済みの sha に到達したか」を判定するために使う
+pub trait ChunkedQuery: GraphQLQuery {
+    type Item;
+
+    fn change_after(vars: &mut Self::Variables, after: Option<String>);
+    fn set_batch(vars: &mut Self::Variables, n: i64);
+    fn process(data: Self::ResponseData) -> Result<(Vec<Self::Item>, Option<String>)>;
+    fn item_sha(item: &Self::Item) -> &str;
+}
+
+/// `Q: ChunkedQuery` を `hasNextPage` が尽きるまで実行し、全ページの `Item` を連結して返す
+pub async fn run_chunked<Q>(
+    client: &Client,
+    url: &str,
+    token: &str,
+    mut variables: Q::Variables,
+    batch: i64,
+) -> Result<Vec<Q::Item>>
+where
+    Q: ChunkedQuery,
+    Q::Variables: Clone,
+{
+    let mut items = Vec::new();
+    Q::set_batch(&mut variables, batch);
+
+    let mut cursor: Option<String> = None;
+    loop {
+        Q::change_after(&mut variables, cursor.clone());
+        let req_body = Q::build_query(variables.clone());
+
+        let res = client
+            .post(url)
+            .bearer_auth(token)
+            .json(&req_body)
+            .send()
+            .await
+            .context("chunked query の呼び出しに失敗")?;
+
+        let response_body: Response<Q::ResponseData> = res
+            .json()
+            .await
+            .context("chunked query レスポンスの JSON パースに失敗")?;
+
+        // data: null は「最終ページ」ではなく GraphQL エラー (レート制限、認証切れ、
+        // 不正なクエリ等) を意味する。ここで握りつぶすと、途中のページが失敗しても
+        // 取得済み分だけの不完全な履歴が成功扱いで返ってしまう
+        if let Some(errors) = &response_body.errors {
+            if !errors.is_empty() {
+                let messages = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; ");
+                anyhow::bail!("chunked query がエラーを返却: {}", messages);
+            }
+        }
+
+        let Some(data) = response_body.data else {
+            anyhow::bail!("chunked query のレスポンスに data がありません (errors も空)");
+        };
+
+        let (mut page_items, next_cursor) = Q::process(data)?;
+        items.append(&mut page_items);
+
+        match next_cursor {
+            Some(c) => cursor = Some(c),
+            None => break,
+        }
+    }
+
+    Ok(items)
+}
+
+/// `run_chunked` と同様にページを辿るが、`stop_sha` に一致する `Item` が現れたページで
+/// 打ち切り、それより新しい分だけを返す (`stop_sha` が `None` なら全件を辿る)
+/// - `--since-last-run` 用: 前回確認済みの sha が見つかった時点でページ取得自体を
+///   止めるので、`run_chunked` で全履歴を取ってから手元でフィルタするより安上がりになる
+/// - 前回の sha が最後まで見つからない場合 (force-push 等) は全履歴を返す
+pub async fn run_chunked_until<Q>(
+    client: &Client,
+    url: &str,
+    token: &str,
+    mut variables: Q::Variables,
+    batch: i64,
+    stop_sha: Option<&str>,
+) -> Result<Vec<Q::Item>>
+where
+    Q: ChunkedQuery,
+    Q::Variables: Clone,
+{
+    let mut items = Vec::new();
+    Q::set_batch(&mut variables, batch);
+
+    let mut cursor: Option<String> = None;
+    loop {
+        Q::change_after(&mut variables, cursor.clone());
+        let req_body = Q::build_query(variables.clone());
+
+        let res = client
+            .post(url)
+            .bearer_auth(token)
+            .json(&req_body)
+            .send()
+            .await
+            .context("chunked query の呼び出しに失敗")?;
+
+        let response_body: Response<Q::ResponseData> = res
+            .json()
+            .await
+            .context("chunked query レスポンスの JSON パースに失敗")?;
+
+        // data: null は「最終ページ」ではなく GraphQL エラー (レート制限、認証切れ、
+        // 不正なクエリ等) を意味する。ここで握りつぶすと、途中のページが失敗しても
+        // 取得済み分だけの不完全な履歴が成功扱いで返ってしまう
+        if let Some(errors) = &response_body.errors {
+            if !errors.is_empty() {
+                let messages = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; ");
+                anyhow::bail!("chunked query がエラーを返却: {}", messages);
+            }
+        }
+
+        let Some(data) = response_body.data else {
+            anyhow::bail!("chunked query のレスポンスに data がありません (errors も空)");
+        };
+
+        let (page_items, next_cursor) = Q::process(data)?;
+
+        if let Some(sha) = stop_sha {
+            if let Some(pos) = page_items.iter().position(|item| Q::item_sha(item) == sha) {
+                items.extend(page_items.into_iter().take(pos));
+                return Ok(items); // 前回確認済みの sha に到達したので、それ以降のページは取らない
+            }
+        }
+        items.extend(page_items);
+
+        match next_cursor {
+            Some(c) => cursor = Some(c),
+            None => break,
+        }
+    }
+
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Clone, Serialize)]
+    struct FakeVariables {
+        cursor: Option<String>,
+        batch: i64,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct FakeResponseData {
+        items: Vec<String>,
+        has_next: bool,
+        end_cursor: Option<String>,
+    }
+
+    /// `ChunkedQuery` を試すための最小のフェイククエリ (テスト専用、実際の GraphQL スキーマは持たない)
+    struct FakeQuery;
+
+    impl GraphQLQuery for FakeQuery {
+        type Variables = FakeVariables;
+        type ResponseData = FakeResponseData;
+
+        fn build_query(variables: Self::Variables) -> graphql_client::QueryBody<Self::Variables> {
+            graphql_client::QueryBody { variables, query: "query Fake { fake }", operation_name: "Fake" }
+        }
+    }
+
+    impl ChunkedQuery for FakeQuery {
+        type Item = String;
+
+        fn change_after(vars: &mut FakeVariables, after: Option<String>) {
+            vars.cursor = after;
+        }
+
+        fn set_batch(vars: &mut FakeVariables, n: i64) {
+            vars.batch = n;
+        }
+
+        fn process(data: FakeResponseData) -> Result<(Vec<String>, Option<String>)> {
+            let next_cursor = if data.has_next { data.end_cursor } else { None };
+            Ok((data.items, next_cursor))
+        }
+
+        fn item_sha(item: &String) -> &str {
+            item
+        }
+    }
+
+    /// `pages` の GraphQL レスポンス本文を、リクエストが来るたびに 1 つずつ返す使い捨てサーバー
+    /// - 本物の GHES/dotcom API の代わりに `run_chunked`/`run_chunked_until` を駆動するために使う
+    fn spawn_mock_server(pages: Vec<String>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("mock server の bind に失敗");
+        let addr = listener.local_addr().expect("mock server のアドレス取得に失敗");
+        let pages = Arc::new(Mutex::new(pages.into_iter()));
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf); // リクエスト本文は使わないので読み捨てる
+
+                let Some(body) = pages.lock().unwrap().next() else { break };
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body,
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        format!("http://{}", addr)
+    }
+
+    fn page_body(items: &[&str], end_cursor: Option<&str>, has_next: bool) -> String {
+        serde_json::json!({
+            "data": {
+                "items": items,
+                "has_next": has_next,
+                "end_cursor": end_cursor,
+            }
+        })
+        .to_string()
+    }
+
+    fn error_body(message: &str) -> String {
+        serde_json::json!({
+            "data": null,
+            "errors": [{ "message": message }],
+        })
+        .to_string()
+    }
+
+    fn variables() -> FakeVariables {
+        FakeVariables { cursor: None, batch: 2 }
+    }
+
+    #[tokio::test]
+    async fn run_chunked_walks_until_has_next_page_is_false() {
+        let url = spawn_mock_server(vec![
+            page_body(&["a", "b"], Some("cursor-1"), true),
+            page_body(&["c"], None, false),
+        ]);
+
+        let items = run_chunked::<FakeQuery>(&Client::new(), &url, "token", variables(), 2)
+            .await
+            .expect("run_chunked が失敗");
+
+        assert_eq!(items, vec!["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn run_chunked_until_stops_at_the_given_sha_without_fetching_further_pages() {
+        let url = spawn_mock_server(vec![
+            page_body(&["a", "b"], Some("cursor-1"), true),
+            // stop_sha がこのページで見つかるはずなので、2 ページ目は要求されない
+            page_body(&["ZZZ-should-not-be-reached"], None, false),
+        ]);
+
+        let items = run_chunked_until::<FakeQuery>(&Client::new(), &url, "token", variables(), 2, Some("b"))
+            .await
+            .expect("run_chunked_until が失敗");
+
+        assert_eq!(items, vec!["a"]);
+    }
+
+    #[tokio::test]
+    async fn run_chunked_until_returns_all_items_when_stop_sha_is_none() {
+        let url = spawn_mock_server(vec![
+            page_body(&["a", "b"], Some("cursor-1"), true),
+            page_body(&["c"], None, false),
+        ]);
+
+        let items = run_chunked_until::<FakeQuery>(&Client::new(), &url, "token", variables(), 2, None)
+            .await
+            .expect("run_chunked_until が失敗");
+
+        assert_eq!(items, vec!["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn run_chunked_fails_on_a_mid_crawl_graphql_error_instead_of_truncating_silently() {
+        let url = spawn_mock_server(vec![
+            page_body(&["a", "b"], Some("cursor-1"), true),
+            // 2 ページ目がレート制限等で失敗した場合、"a, b" だけの成功扱いで
+            // 返してしまってはいけない
+            error_body("rate limited"),
+        ]);
+
+        let err = run_chunked::<FakeQuery>(&Client::new(), &url, "token", variables(), 2)
+            .await
+            .expect_err("GraphQL エラーを含むレスポンスは Err になるべき");
+
+        assert!(err.to_string().contains("rate limited"), "unexpected error: {err}");
+    }
+}
@@ -0,0 +1,37 @@
+/* forge.rs
+    GHES REST + GraphQL に決め打ちだった検索ロジックを抽象化するトレイト
+    実装の背景:
+    - URL 組み立てが search_repos_with_file / ensure_repo_info / fetch_latest_commit_for_path に
+      散らばっており、GitHub.com や Gitea/Forgejo など別のフォージに対応させづらかった
+    - `Forge` を実装したバックエンドを用意し (ghes は常に有効、dotcom/gitea は同名の
+      cargo feature で有効化)、`FORGE_KIND` 環境変数でどれを使うか選べるようにする
+*/
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::{CommitInfo, RepoTarget};
+
+/// 1 つの Git フォージ (GHES / GitHub.com / Gitea 等) に対する検索・コミット取得の抽象
+#[async_trait]
+pub trait Forge {
+    /// `filename` を含むリポジトリを列挙する
+    async fn search_files(&self, filename: &str) -> Result<Vec<RepoTarget>>;
+
+    /// `target` のコミット履歴を取得する (存在しない/空なら空の Vec)
+    async fn latest_commit(&self, target: &RepoTarget) -> Result<Vec<CommitInfo>>;
+
+    /// `target` の最新コミット 1 件だけを軽量に取得する (存在しなければ `None`)
+    /// - `interactive::pick_targets` がまだ取得していない候補にハイライトが移るたびに呼ぶため、
+    ///   `latest_commit` のような全履歴走査はしない実装にすること
+    async fn latest_commit_preview(&self, target: &RepoTarget) -> Result<Option<CommitInfo>>;
+
+    /// `since_sha` より新しいコミットだけを取得する (`None` なら全履歴)
+    /// - `--since-last-run` 用: `latest_commit` のように全履歴を取得してから手元で
+    ///   フィルタするのではなく、`since_sha` に到達した時点でページ取得自体を打ち切る実装にすること
+    async fn commits_since(&self, target: &RepoTarget, since_sha: Option<&str>) -> Result<Vec<CommitInfo>>;
+
+    /// `--interactive` で clone する際に `owner/repo.git` の前に付ける URL
+    /// - GHES/Gitea は設定された base URL、GitHub.com は固定で `https://github.com`
+    fn clone_base_url(&self) -> String;
+}
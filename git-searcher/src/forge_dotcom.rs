@@ -0,0 +1,206 @@
+/* forge_dotcom.rs
+    GitHub.com 向けの `Forge` 実装 (cargo feature "dotcom")
+    - REST `/search/code` は GHES と同じ形だが `/api/v3` プレフィックスを持たない
+    - GitHub.com の GraphQL スキーマは `object(expression:)` を直接呼べるため、
+      GHES 版 (`FileBlame`) が使う `defaultBranchRef.history` の workaround を踏まず、
+      `FileBlameDotcom` で `expression: "HEAD"` から直接 Commit を引く
+*/
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+use crate::chunked_query::{run_chunked, run_chunked_until, ChunkedQuery};
+use crate::forge::Forge;
+use crate::query::file_blame_dotcom::Variables as FileBlameDotcomVariables;
+use crate::query::{self, FileBlameDotcom};
+use crate::{CommitInfo, RepoTarget};
+use graphql_client::{GraphQLQuery, Response};
+
+/// `object(expression:)` に渡すデフォルトブランチの式
+/// - ブランチ名を問わず常に既定ブランチの先頭を指す省略形
+const DEFAULT_BRANCH_EXPRESSION: &str = "HEAD";
+
+/// 1 ページあたりに取得するコミット履歴の件数
+const HISTORY_BATCH: i64 = 100;
+
+const REST_BASE: &str = "https://api.github.com";
+const GRAPHQL_URL: &str = "https://api.github.com/graphql";
+
+/// GitHub.com 向け `Forge` 実装
+pub struct DotcomForge {
+    token: String,
+    rest: Client,
+    graphql: Client,
+}
+
+impl DotcomForge {
+    pub fn new(token: &str) -> Self {
+        Self {
+            token: token.to_string(),
+            rest: Client::new(),
+            graphql: Client::new(),
+        }
+    }
+
+    /// `query::CommitNode` を表示用の `CommitInfo` に変換する
+    fn node_to_commit_info(&self, target: &RepoTarget, node: query::CommitNode) -> CommitInfo {
+        CommitInfo {
+            repo_full: format!("{}/{}", target.owner, target.repo),
+            path: target.path.clone(),
+            url: format!("https://github.com/{}/{}", target.owner, target.repo),
+            login: node.login,
+            sha: node.sha,
+            date: node.date,
+        }
+    }
+}
+
+#[async_trait]
+impl Forge for DotcomForge {
+    ///--------------------------------------
+    /// REST: /search/code で filename マッチを全ページ走査 (GHES と同じ形、prefix なし)
+    ///--------------------------------------
+    async fn search_files(&self, filename: &str) -> Result<Vec<RepoTarget>> {
+        let mut set: BTreeSet<(String, String)> = BTreeSet::new(); // (repo_full, path)
+        let search_url = format!("{}/search/code", REST_BASE);
+        let mut page = 1usize;
+
+        loop {
+            let resp = self
+                .rest
+                .get(&search_url)
+                .bearer_auth(&self.token)
+                .query(&[
+                    ("q", format!("filename:{}", filename)),
+                    ("per_page", "100".to_string()),
+                    ("page", page.to_string()),
+                ])
+                .send()
+                .await?
+                .error_for_status()
+                .with_context(|| format!("search/code(page={}) の呼び出しに失敗", page))?;
+
+            let body: Value = resp.json().await.context("search/code の JSON パースに失敗")?;
+            let items = body["items"].as_array().cloned().unwrap_or_default();
+            if items.is_empty() {
+                break; // ページ終端
+            }
+
+            for item in items {
+                if let (Some(repo_full), Some(path)) = (
+                    item["repository"]["full_name"].as_str(),
+                    item["path"].as_str(),
+                ) {
+                    set.insert((repo_full.to_string(), path.to_string()));
+                }
+            }
+
+            page += 1;
+        }
+
+        let targets = set
+            .into_iter()
+            .map(|(repo_full, path)| {
+                let (owner, repo) = repo_full.split_once('/').expect("Invalid repo format");
+                RepoTarget { owner: owner.to_string(), repo: repo.to_string(), path }
+            })
+            .collect();
+
+        Ok(targets)
+    }
+
+    ///--------------------------------------
+    /// GraphQL: `object(expression: "HEAD")` から直接 Commit を引き、
+    /// 指定 path の全コミット履歴を取得する
+    ///--------------------------------------
+    async fn latest_commit(&self, target: &RepoTarget) -> Result<Vec<CommitInfo>> {
+        let variables = FileBlameDotcomVariables {
+            owner: target.owner.clone(),
+            repo: target.repo.clone(),
+            expression: DEFAULT_BRANCH_EXPRESSION.to_string(),
+            path: target.path.clone(),
+            batch: HISTORY_BATCH,
+            cursor: None,
+        };
+
+        let nodes = run_chunked::<FileBlameDotcom>(&self.graphql, GRAPHQL_URL, &self.token, variables, HISTORY_BATCH)
+            .await
+            .with_context(|| format!("コミット履歴の取得に失敗: {}/{}", target.owner, target.repo))?;
+
+        let history = nodes.into_iter().map(|node| self.node_to_commit_info(target, node)).collect();
+
+        Ok(history)
+    }
+
+    ///--------------------------------------
+    /// GraphQL: `history(path:, first: 1)` を 1 ページだけ取得し、最新の 1 件だけ返す
+    /// - GHES 版と同じ理由で、interactive のハイライト行プレビューのような
+    ///   頻繁に呼ばれる場面向けに全履歴を辿らない軽量フェッチにしている
+    ///--------------------------------------
+    async fn latest_commit_preview(&self, target: &RepoTarget) -> Result<Option<CommitInfo>> {
+        let variables = FileBlameDotcomVariables {
+            owner: target.owner.clone(),
+            repo: target.repo.clone(),
+            expression: DEFAULT_BRANCH_EXPRESSION.to_string(),
+            path: target.path.clone(),
+            batch: 1,
+            cursor: None,
+        };
+        let req_body = FileBlameDotcom::build_query(variables);
+
+        let res = self
+            .graphql
+            .post(GRAPHQL_URL)
+            .bearer_auth(&self.token)
+            .json(&req_body)
+            .send()
+            .await
+            .with_context(|| format!("コミットプレビューの取得に失敗: {}/{}", target.owner, target.repo))?;
+
+        let response_body: Response<query::file_blame_dotcom::ResponseData> = res
+            .json()
+            .await
+            .context("コミットプレビューレスポンスの JSON パースに失敗")?;
+
+        let Some(data) = response_body.data else {
+            return Ok(None);
+        };
+
+        let (items, _) = <FileBlameDotcom as ChunkedQuery>::process(data)?;
+        Ok(items.into_iter().next().map(|node| self.node_to_commit_info(target, node)))
+    }
+
+    fn clone_base_url(&self) -> String {
+        "https://github.com".to_string()
+    }
+
+    ///--------------------------------------
+    /// GraphQL: `since_sha` に到達するまでだけページを辿る (GHES と同じ理由)
+    ///--------------------------------------
+    async fn commits_since(&self, target: &RepoTarget, since_sha: Option<&str>) -> Result<Vec<CommitInfo>> {
+        let variables = FileBlameDotcomVariables {
+            owner: target.owner.clone(),
+            repo: target.repo.clone(),
+            expression: DEFAULT_BRANCH_EXPRESSION.to_string(),
+            path: target.path.clone(),
+            batch: HISTORY_BATCH,
+            cursor: None,
+        };
+
+        let nodes = run_chunked_until::<FileBlameDotcom>(
+            &self.graphql,
+            GRAPHQL_URL,
+            &self.token,
+            variables,
+            HISTORY_BATCH,
+            since_sha,
+        )
+        .await
+        .with_context(|| format!("差分コミット履歴の取得に失敗: {}/{}", target.owner, target.repo))?;
+
+        Ok(nodes.into_iter().map(|node| self.node_to_commit_info(target, node)).collect())
+    }
+}
@@ -0,0 +1,379 @@
+/* forge_ghes.rs
+    GitHub Enterprise Server (GHES) 向けの `Forge` 実装
+    - REST `/search/code` (または GraphQL `search(type: REPOSITORY)`) でファイルを含む
+      リポジトリを列挙し、GraphQL `defaultBranchRef.history(path:)` でコミット履歴を取る
+    - GHES は GraphQL の `object(expression:)` が使えないため、history 経由の
+      ワークアラウンドが必要 (DotcomForge 側のコメント参照)
+*/
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use graphql_client::{GraphQLQuery, Response};
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::env;
+use tokio::time::{sleep, Duration};
+
+use crate::chunked_query::{run_chunked, run_chunked_until, ChunkedQuery};
+use crate::forge::Forge;
+use crate::query::file_blame::Variables as FileBlameVariables;
+use crate::query::repo_search::{
+    ResponseData as RepoSearchResponseData, Variables as RepoSearchVariables,
+};
+use crate::query::{self, FileBlame, RepoSearch};
+use crate::{CommitInfo, RepoTarget};
+
+/// 1 ページあたりに取得するコミット履歴の件数
+const HISTORY_BATCH: i64 = 100;
+
+/// REST の `/search/code` を使うか、GraphQL のカーソル列挙を使うか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SearchMode {
+    Rest,
+    Graphql,
+}
+
+/// GHES 向け `Forge` 実装
+pub struct GhesForge {
+    ghe_url: String,
+    graphql_url: String,
+    token: String,
+    search_mode: SearchMode,
+    rest: Client,
+    graphql: Client,
+}
+
+impl GhesForge {
+    /// `GHE_URL`/`GITHUB_TOKEN`/`SEARCH_MODE` から GHES バックエンドを組み立てる
+    pub fn new(ghe_url: &str, token: &str) -> Self {
+        let ghe_url = ghe_url.trim_end_matches('/').to_string();
+        let graphql_url = format!("{}/api/graphql", ghe_url);
+
+        // SEARCH_MODE: "rest" (既定、/search/code) か "graphql" (search(type: REPOSITORY) で全件列挙) かを選択
+        let search_mode = match env::var("SEARCH_MODE").unwrap_or_else(|_| "rest".to_string()).as_str() {
+            "graphql" => SearchMode::Graphql,
+            _ => SearchMode::Rest,
+        };
+
+        Self {
+            ghe_url,
+            graphql_url,
+            token: token.to_string(),
+            search_mode,
+            rest: Client::new(),
+            graphql: Client::new(),
+        }
+    }
+
+    ///--------------------------------------
+    /// REST: /search/code で filename マッチを全ページ走査
+    /// - 戻り値は重複を排した RepoTarget のベクタ
+    ///--------------------------------------
+    async fn search_repos_with_file(&self, filename: &str) -> Result<Vec<RepoTarget>> {
+        let mut set: BTreeSet<(String, String)> = BTreeSet::new(); // (repo_full, path)
+
+        // GHES の search API は GitHub.com と同様に利用可能
+        let search_url = format!("{}/api/v3/search/code", self.ghe_url);
+        let mut page = 1usize;
+
+        loop {
+            let resp = self
+                .rest
+                .get(&search_url)
+                .bearer_auth(&self.token)
+                .query(&[
+                    ("q", format!("filename:{}", filename)),
+                    ("per_page", "100".to_string()),
+                    ("page", page.to_string()),
+                ])
+                .send()
+                .await?
+                .error_for_status()
+                .with_context(|| format!("search/code(page={}) の呼び出しに失敗", page))?;
+
+            // JSON 文字列を serde_json::Value にデコード
+            let body: Value = resp.json().await.context("search/code の JSON パースに失敗")?;
+
+            let items = body["items"].as_array().cloned().unwrap_or_default();
+            if items.is_empty() {
+                break; // ページ終端
+            }
+
+            for item in items {
+                if let (Some(repo_full), Some(path)) = (
+                    item["repository"]["full_name"].as_str(),
+                    item["path"].as_str(),
+                ) {
+                    set.insert((repo_full.to_string(), path.to_string()));
+                }
+            }
+
+            page += 1;
+            // ページまたぎの過負荷対策
+            sleep(Duration::from_millis(250)).await;
+        }
+
+        Ok(set_to_targets(set))
+    }
+
+    ///--------------------------------------
+    /// GraphQL: search(type: REPOSITORY) をカーソルで全件列挙し、
+    /// ヒットしたリポジトリごとに defaultBranchRef.history(path:) でファイルの有無を確認
+    /// - REST の /search/code は 1000 件で打ち切られるため、数万リポジトリ規模の
+    ///   インスタンスを網羅したい場合はこちらを使う (SEARCH_MODE=graphql)
+    /// - `filename:` はコード検索の修飾子であり `type: REPOSITORY` では無効 (リポジトリの
+    ///   メタデータにマッチしようとして実質ヒットしない) なので、`q` には全リポジトリに
+    ///   マッチする広いクエリを渡し、列挙した各リポジトリを `fetch_first_page` で個別に
+    ///   プローブしてファイルの有無を確定させる
+    ///--------------------------------------
+    async fn search_repos_with_file_graphql(&self, filename: &str) -> Result<Vec<RepoTarget>> {
+        let mut set: BTreeSet<(String, String)> = BTreeSet::new(); // (repo_full, path)
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let variables = RepoSearchVariables {
+                // リポジトリ検索用の広いクエリ (`created:>2000-01-01` は実質すべての
+                // リポジトリにマッチする)。`filename:` はコード検索専用の修飾子なので
+                // ここでは使えない
+                q: "created:>2000-01-01".to_string(),
+                cursor: cursor.clone(),
+            };
+            let req_body = RepoSearch::build_query(variables);
+
+            let res = self
+                .graphql
+                .post(&self.graphql_url)
+                .bearer_auth(&self.token)
+                .json(&req_body)
+                .send()
+                .await
+                .context("GraphQL search(type: REPOSITORY) の呼び出しに失敗")?;
+
+            let response_body: Response<RepoSearchResponseData> = res
+                .json()
+                .await
+                .context("GraphQL search(type: REPOSITORY) レスポンスの JSON パースに失敗")?;
+
+            // data: null は「列挙完了」ではなく GraphQL エラーを意味する。ここで
+            // 握りつぶすと、途中のページが失敗しても取得済み分だけの不完全な
+            // リポジトリ一覧が成功扱いで返ってしまい、このモードが網羅を
+            // 謳っている意味がなくなる
+            if let Some(errors) = &response_body.errors {
+                if !errors.is_empty() {
+                    let messages = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; ");
+                    anyhow::bail!("GraphQL search(type: REPOSITORY) がエラーを返却: {}", messages);
+                }
+            }
+
+            let Some(search) = response_body.data.as_ref().map(|d| &d.search) else {
+                anyhow::bail!("GraphQL search(type: REPOSITORY) のレスポンスに data がありません (errors も空)");
+            };
+
+            for edge in search.edges.iter().flatten().flatten() {
+                let Some(node) = edge.node.as_ref() else { continue };
+                let query::repo_search::RepoSearchSearchEdgesNode::Repository(repo) = node else {
+                    continue; // Repository 以外のノードは来ない想定
+                };
+
+                let Some((owner, repo_name)) = repo.name_with_owner.split_once('/') else {
+                    continue;
+                };
+                let candidate = RepoTarget {
+                    owner: owner.to_string(),
+                    repo: repo_name.to_string(),
+                    path: filename.to_string(),
+                };
+
+                // ここで実際に defaultBranchRef.history(path:) を 1 ページだけ問い合わせ、
+                // ファイルが存在するリポジトリだけを残す
+                if !self.fetch_first_page(&candidate).await?.is_empty() {
+                    set.insert((repo.name_with_owner.clone(), filename.to_string()));
+                }
+
+                // リポジトリごとのプローブ呼び出しの過負荷対策
+                sleep(Duration::from_millis(50)).await;
+            }
+
+            if !search.page_info.has_next_page {
+                break;
+            }
+            cursor = search.page_info.end_cursor.clone();
+
+            // ページまたぎの過負荷対策
+            sleep(Duration::from_millis(250)).await;
+        }
+
+        Ok(set_to_targets(set))
+    }
+
+    ///--------------------------------------
+    /// GraphQL: `defaultBranchRef.history(path:)` を 1 ページ (first: 1) だけ問い合わせる
+    /// - `search_repos_with_file_graphql` の候補絞り込みや `latest_commit_preview` の
+    ///   ような、「最新の 1 件 (の有無) だけ分かればいい」場面向けの軽量フェッチで、
+    ///   `latest_commit` のように `hasNextPage` を全て辿ることはしない
+    ///--------------------------------------
+    async fn fetch_first_page(&self, target: &RepoTarget) -> Result<Vec<query::CommitNode>> {
+        let variables = FileBlameVariables {
+            owner: target.owner.clone(),
+            repo: target.repo.clone(),
+            path: target.path.clone(),
+            batch: 1,
+            cursor: None,
+        };
+        let req_body = FileBlame::build_query(variables);
+
+        let res = self
+            .graphql
+            .post(&self.graphql_url)
+            .bearer_auth(&self.token)
+            .json(&req_body)
+            .send()
+            .await
+            .with_context(|| format!("ファイル有無の確認に失敗: {}/{}", target.owner, target.repo))?;
+
+        let response_body: Response<query::file_blame::ResponseData> = res
+            .json()
+            .await
+            .context("ファイル有無確認レスポンスの JSON パースに失敗")?;
+
+        let Some(data) = response_body.data else {
+            return Ok(Vec::new());
+        };
+
+        let (items, _) = <FileBlame as ChunkedQuery>::process(data)?;
+        Ok(items)
+    }
+
+    ///--------------------------------------
+    /// REST: /repos/{owner}/{repo} で default_branch 確認（任意）
+    /// - なくても GraphQL は動くことが多いが、健全性チェックとして保持
+    ///--------------------------------------
+    async fn ensure_repo_info(&self, target: &RepoTarget) -> Result<()> {
+        let url = format!("{}/api/v3/repos/{}/{}", self.ghe_url, target.owner, target.repo);
+
+        // 基本使わないが API レベルでのリポジトリ確認
+        let info: Value = self
+            .rest
+            .get(&url)
+            .bearer_auth(&self.token)
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("GET {} に失敗", url))?
+            .json()
+            .await
+            .context("repo info JSON パースに失敗")?;
+
+        if info["default_branch"].is_null() {
+            // ここでは警告に留める（GraphQL で defaultBranchRef がなくても safe にハンドリング）
+            eprintln!("⚠️ default_branch が取得できません: {}/{}", target.owner, target.repo);
+        }
+        Ok(())
+    }
+
+    /// `query::CommitNode` を表示用の `CommitInfo` に変換する
+    fn node_to_commit_info(&self, target: &RepoTarget, node: query::CommitNode) -> CommitInfo {
+        CommitInfo {
+            repo_full: format!("{}/{}", target.owner, target.repo),
+            path: target.path.clone(),
+            url: format!("{}/{}/{}", self.ghe_url, target.owner, target.repo),
+            login: node.login,
+            sha: node.sha,
+            date: node.date,
+        }
+    }
+}
+
+/// (repo_full, path) の集合を RepoTarget の Vec に変換する
+fn set_to_targets(set: BTreeSet<(String, String)>) -> Vec<RepoTarget> {
+    set.into_iter()
+        .map(|(repo_full, path)| {
+            let (owner, repo) = repo_full.split_once('/').expect("Invalid repo format");
+            RepoTarget { owner: owner.to_string(), repo: repo.to_string(), path }
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Forge for GhesForge {
+    async fn search_files(&self, filename: &str) -> Result<Vec<RepoTarget>> {
+        match self.search_mode {
+            SearchMode::Rest => self.search_repos_with_file(filename).await,
+            SearchMode::Graphql => self.search_repos_with_file_graphql(filename).await,
+        }
+    }
+
+    ///--------------------------------------
+    /// GraphQL: 指定 path の全コミット履歴を取得
+    /// - `ChunkedQuery` 経由で `hasNextPage` が尽きるまでページを辿り、
+    ///   そのファイルの全履歴を積み上げる
+    /// - defaultBranchRef がない/履歴が空のリポジトリは空の Vec を返す
+    ///--------------------------------------
+    async fn latest_commit(&self, target: &RepoTarget) -> Result<Vec<CommitInfo>> {
+        // 健全性チェック（任意）
+        let _ = self.ensure_repo_info(target).await;
+
+        let variables = FileBlameVariables {
+            owner: target.owner.clone(),
+            repo: target.repo.clone(),
+            path: target.path.clone(),
+            batch: HISTORY_BATCH,
+            cursor: None,
+        };
+
+        let nodes = run_chunked::<FileBlame>(&self.graphql, &self.graphql_url, &self.token, variables, HISTORY_BATCH)
+            .await
+            .with_context(|| format!("コミット履歴の取得に失敗: {}/{}", target.owner, target.repo))?;
+
+        let history = nodes.into_iter().map(|node| self.node_to_commit_info(target, node)).collect();
+
+        Ok(history)
+    }
+
+    ///--------------------------------------
+    /// GraphQL: `history(path:, first: 1)` を 1 ページだけ取得し、最新の 1 件だけ返す
+    /// - `latest_commit` のように `hasNextPage` を全て辿らないので、interactive の
+    ///   ハイライト行プレビューのように頻繁に呼ばれる場面でも軽い
+    ///--------------------------------------
+    async fn latest_commit_preview(&self, target: &RepoTarget) -> Result<Option<CommitInfo>> {
+        let nodes = self
+            .fetch_first_page(target)
+            .await
+            .with_context(|| format!("コミットプレビューの取得に失敗: {}/{}", target.owner, target.repo))?;
+
+        Ok(nodes.into_iter().next().map(|node| self.node_to_commit_info(target, node)))
+    }
+
+    fn clone_base_url(&self) -> String {
+        self.ghe_url.clone()
+    }
+
+    ///--------------------------------------
+    /// GraphQL: `since_sha` に到達するまでだけページを辿る
+    /// - `run_chunked_until` が stop_sha の乗ったページで打ち切るため、`latest_commit` と
+    ///   違って全履歴を毎回辿らずに済む (`--since-last-run` 向け)
+    ///--------------------------------------
+    async fn commits_since(&self, target: &RepoTarget, since_sha: Option<&str>) -> Result<Vec<CommitInfo>> {
+        let variables = FileBlameVariables {
+            owner: target.owner.clone(),
+            repo: target.repo.clone(),
+            path: target.path.clone(),
+            batch: HISTORY_BATCH,
+            cursor: None,
+        };
+
+        let nodes = run_chunked_until::<FileBlame>(
+            &self.graphql,
+            &self.graphql_url,
+            &self.token,
+            variables,
+            HISTORY_BATCH,
+            since_sha,
+        )
+        .await
+        .with_context(|| format!("差分コミット履歴の取得に失敗: {}/{}", target.owner, target.repo))?;
+
+        Ok(nodes.into_iter().map(|node| self.node_to_commit_info(target, node)).collect())
+    }
+}
@@ -0,0 +1,257 @@
+/* forge_gitea.rs
+    Gitea/Forgejo 向けの `Forge` 実装 (cargo feature "gitea")
+    - Gitea には GHES/GitHub.com のような GraphQL API が無いため REST のみで完結させる
+    - `/api/v1/repos/search` でインスタンス内の全リポジトリを列挙し、各リポジトリの
+      `/api/v1/repos/{owner}/{repo}/commits?path=` (path 指定に対応) でその
+      ファイルのコミット履歴が取れるかどうかを確認する
+*/
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+
+use crate::forge::Forge;
+use crate::{CommitInfo, RepoTarget};
+
+/// Gitea/Forgejo 向け `Forge` 実装
+pub struct GiteaForge {
+    base_url: String,
+    token: String,
+    rest: Client,
+}
+
+impl GiteaForge {
+    /// `GHE_URL` を Gitea/Forgejo インスタンスのベース URL として流用する
+    pub fn new(base_url: &str, token: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            rest: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Forge for GiteaForge {
+    ///--------------------------------------
+    /// REST: /api/v1/repos/search でリポジトリを列挙し、各リポジトリに対して
+    /// commits?path= を叩いて filename のコミット履歴があるものだけを残す
+    /// - Gitea の repos/search はコード内容までは検索しないため、インスタンス内の
+    ///   全リポジトリを候補として流し、path フィルタで絞り込む
+    ///--------------------------------------
+    async fn search_files(&self, filename: &str) -> Result<Vec<RepoTarget>> {
+        let mut targets = Vec::new();
+        let search_url = format!("{}/api/v1/repos/search", self.base_url);
+        let mut page = 1usize;
+
+        loop {
+            let resp = self
+                .rest
+                .get(&search_url)
+                .bearer_auth(&self.token)
+                .query(&[("limit", "50".to_string()), ("page", page.to_string())])
+                .send()
+                .await?
+                .error_for_status()
+                .with_context(|| format!("repos/search(page={}) の呼び出しに失敗", page))?;
+
+            let body: Value = resp.json().await.context("repos/search の JSON パースに失敗")?;
+            let items = body["data"].as_array().cloned().unwrap_or_default();
+            if items.is_empty() {
+                break; // ページ終端
+            }
+
+            for item in &items {
+                let (Some(owner), Some(repo)) = (
+                    item["owner"]["login"].as_str(),
+                    item["name"].as_str(),
+                ) else {
+                    continue;
+                };
+
+                let target = RepoTarget {
+                    owner: owner.to_string(),
+                    repo: repo.to_string(),
+                    path: filename.to_string(),
+                };
+
+                // そのファイルのコミット履歴が無いリポジトリは候補から外す。存在確認だけ
+                // なので最大 100 件を取る latest_commit ではなく limit=1 の preview で済ませる
+                if self.latest_commit_preview(&target).await?.is_some() {
+                    targets.push(target);
+                }
+            }
+
+            page += 1;
+        }
+
+        Ok(targets)
+    }
+
+    ///--------------------------------------
+    /// REST: /api/v1/repos/{owner}/{repo}/commits?path={path} でコミット履歴を取得
+    ///--------------------------------------
+    async fn latest_commit(&self, target: &RepoTarget) -> Result<Vec<CommitInfo>> {
+        self.fetch_commits(target, 100).await
+    }
+
+    ///--------------------------------------
+    /// REST: `limit=1` で最新の 1 件だけ取得する
+    /// - interactive のハイライト行プレビューのように頻繁に呼ばれる場面向けの軽量フェッチ
+    ///--------------------------------------
+    async fn latest_commit_preview(&self, target: &RepoTarget) -> Result<Option<CommitInfo>> {
+        Ok(self.fetch_commits(target, 1).await?.into_iter().next())
+    }
+
+    fn clone_base_url(&self) -> String {
+        self.base_url.clone()
+    }
+
+    ///--------------------------------------
+    /// REST: `since_sha` が現れたページで打ち切りながら commits をページングする
+    /// - Gitea の commits API には「この sha 以降」を直接指定するクエリが無いため、
+    ///   `fetch_commits(limit=...)` の 1 回取得とは別に自前でページを進め、
+    ///   `since_sha` の乗ったページが来たらそこで止める (`--since-last-run` 向け)
+    ///--------------------------------------
+    async fn commits_since(&self, target: &RepoTarget, since_sha: Option<&str>) -> Result<Vec<CommitInfo>> {
+        self.fetch_commits_since(target, since_sha).await
+    }
+}
+
+impl GiteaForge {
+    ///--------------------------------------
+    /// REST: /api/v1/repos/{owner}/{repo}/commits?path={path}&limit={limit}
+    ///--------------------------------------
+    async fn fetch_commits(&self, target: &RepoTarget, limit: u32) -> Result<Vec<CommitInfo>> {
+        let url = format!(
+            "{}/api/v1/repos/{}/{}/commits",
+            self.base_url, target.owner, target.repo
+        );
+
+        let resp = self
+            .rest
+            .get(&url)
+            .bearer_auth(&self.token)
+            .query(&[("path", target.path.as_str()), ("limit", &limit.to_string())])
+            .send()
+            .await
+            .with_context(|| format!("GET {} に失敗", url))?;
+
+        if !resp.status().is_success() {
+            // path にマッチするコミットが無い/リポジトリが空 などは履歴なし扱い
+            return Ok(Vec::new());
+        }
+
+        let commits: Value = resp.json().await.context("commits の JSON パースに失敗")?;
+        let repo_full = format!("{}/{}", target.owner, target.repo);
+        let repo_url = format!("{}/{}/{}", self.base_url, target.owner, target.repo);
+
+        let history = commits
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|c| {
+                let sha = c["sha"].as_str().unwrap_or("-").to_string();
+                let date = c["commit"]["author"]["date"].as_str().unwrap_or("-").to_string();
+                let login = c["author"]["login"]
+                    .as_str()
+                    .map(|s| s.to_string())
+                    .or_else(|| c["commit"]["author"]["name"].as_str().map(|s| s.to_string()))
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                CommitInfo {
+                    repo_full: repo_full.clone(),
+                    path: target.path.clone(),
+                    url: repo_url.clone(),
+                    login,
+                    sha,
+                    date,
+                }
+            })
+            .collect();
+
+        Ok(history)
+    }
+
+    const COMMITS_PAGE_SIZE: u32 = 50;
+
+    ///--------------------------------------
+    /// REST: /api/v1/repos/{owner}/{repo}/commits?path={path}&page={page} をページングし、
+    /// `since_sha` と一致するコミットが現れたページでそれ以上のページ取得を打ち切る
+    /// (`since_sha` が `None` なら最後まで辿る)
+    ///--------------------------------------
+    async fn fetch_commits_since(
+        &self,
+        target: &RepoTarget,
+        since_sha: Option<&str>,
+    ) -> Result<Vec<CommitInfo>> {
+        let url = format!(
+            "{}/api/v1/repos/{}/{}/commits",
+            self.base_url, target.owner, target.repo
+        );
+        let repo_full = format!("{}/{}", target.owner, target.repo);
+        let repo_url = format!("{}/{}/{}", self.base_url, target.owner, target.repo);
+
+        let mut history = Vec::new();
+        let mut page = 1u32;
+
+        loop {
+            let resp = self
+                .rest
+                .get(&url)
+                .bearer_auth(&self.token)
+                .query(&[
+                    ("path", target.path.as_str()),
+                    ("limit", &Self::COMMITS_PAGE_SIZE.to_string()),
+                    ("page", &page.to_string()),
+                ])
+                .send()
+                .await
+                .with_context(|| format!("GET {} に失敗", url))?;
+
+            if !resp.status().is_success() {
+                break; // path にマッチするコミットが無い/リポジトリが空 などは履歴なし扱い
+            }
+
+            let commits: Value = resp.json().await.context("commits の JSON パースに失敗")?;
+            let items = commits.as_array().cloned().unwrap_or_default();
+            if items.is_empty() {
+                break; // ページ終端
+            }
+
+            let mut reached_since = false;
+            for c in items {
+                let sha = c["sha"].as_str().unwrap_or("-").to_string();
+                if since_sha == Some(sha.as_str()) {
+                    reached_since = true;
+                    break; // 前回確認済みの sha に到達、それ以降のページは取らない
+                }
+
+                let date = c["commit"]["author"]["date"].as_str().unwrap_or("-").to_string();
+                let login = c["author"]["login"]
+                    .as_str()
+                    .map(|s| s.to_string())
+                    .or_else(|| c["commit"]["author"]["name"].as_str().map(|s| s.to_string()))
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                history.push(CommitInfo {
+                    repo_full: repo_full.clone(),
+                    path: target.path.clone(),
+                    url: repo_url.clone(),
+                    login,
+                    sha,
+                    date,
+                });
+            }
+
+            if reached_since {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(history)
+    }
+}
@@ -0,0 +1,206 @@
+/* interactive.rs
+    --interactive 時に検索結果をその場でファジーフィルタしながら選べるターミナル UI
+    実装の背景:
+    - バッチで print するだけでは「見つけたリポジトリのうちどれを取ってくるか」を
+      その場で決められない。入力しながら絞り込み、複数選択してまとめて
+      git clone できるようにし、gitnow のインタラクティブなワークフローを真似ている
+    - 選択中の行だけ CommitInfo を遅延取得して表示することで、全件を事前に
+      取得する必要をなくしている
+*/
+
+use anyhow::{Context, Result};
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType};
+use crossterm::{cursor, execute, queue};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io::{stdout, Write};
+use std::process::Command;
+use std::time::Duration;
+
+use crate::forge::Forge;
+use crate::{CommitInfo, RepoTarget};
+
+/// ファジーフィルタ: `needle` の文字が `haystack` に順序通り現れれば一致とみなす
+fn fuzzy_match(haystack: &str, needle: &str) -> bool {
+    if needle.is_empty() {
+        return true;
+    }
+    let mut chars = haystack.chars();
+    needle.chars().all(|nc| chars.any(|hc| hc.eq_ignore_ascii_case(&nc)))
+}
+
+/// ファジーフィルタ + 多重選択を行い、選ばれた `RepoTarget` を返す（空なら中止）
+async fn pick_targets(forge: &dyn Forge, targets: &[RepoTarget]) -> Result<Vec<RepoTarget>> {
+    enable_raw_mode().context("ターミナルを raw mode にできません")?;
+    let mut out = stdout();
+
+    let mut query = String::new();
+    let mut selected = vec![false; targets.len()];
+    let mut cursor_idx = 0usize;
+    let mut commit_cache: HashMap<usize, Option<CommitInfo>> = HashMap::new();
+
+    let picked = loop {
+        let filtered: Vec<usize> = targets
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| fuzzy_match(&format!("{}/{}", t.owner, t.repo), &query))
+            .map(|(i, _)| i)
+            .collect();
+
+        if cursor_idx >= filtered.len() {
+            cursor_idx = filtered.len().saturating_sub(1);
+        }
+
+        // ハイライト中の候補のコミット情報を一度だけ遅延取得して表示に使う
+        // (全履歴を辿る `latest_commit` ではなく、1 ページだけの軽量な
+        // `latest_commit_preview` を使う。カーソル移動のたびに呼ばれるため)
+        if let Some(&idx) = filtered.get(cursor_idx) {
+            if let Entry::Vacant(entry) = commit_cache.entry(idx) {
+                let info = forge.latest_commit_preview(&targets[idx]).await.ok().flatten();
+                entry.insert(info);
+            }
+        }
+
+        render(&mut out, &query, targets, &filtered, cursor_idx, &selected, &commit_cache)?;
+
+        if !event::poll(Duration::from_millis(200))? {
+            continue;
+        }
+
+        if let Event::Key(key) = event::read()? {
+            match key.code {
+                KeyCode::Esc => break Vec::new(),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break Vec::new(),
+                KeyCode::Enter => {
+                    let chosen: Vec<RepoTarget> = targets
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| selected[*i])
+                        .map(|(_, t)| t.clone())
+                        .collect();
+                    break if chosen.is_empty() {
+                        // 何も選んでいなければハイライト中の 1 件を対象にする
+                        filtered
+                            .get(cursor_idx)
+                            .map(|&i| vec![targets[i].clone()])
+                            .unwrap_or_default()
+                    } else {
+                        chosen
+                    };
+                }
+                KeyCode::Char(' ') => {
+                    if let Some(&idx) = filtered.get(cursor_idx) {
+                        selected[idx] = !selected[idx];
+                    }
+                }
+                KeyCode::Down => cursor_idx = (cursor_idx + 1).min(filtered.len().saturating_sub(1)),
+                KeyCode::Up => cursor_idx = cursor_idx.saturating_sub(1),
+                KeyCode::Backspace => {
+                    query.pop();
+                    cursor_idx = 0;
+                }
+                KeyCode::Char(c) => {
+                    query.push(c);
+                    cursor_idx = 0;
+                }
+                _ => {}
+            }
+        }
+    };
+
+    disable_raw_mode().ok();
+    execute!(out, cursor::Show).ok();
+    println!();
+    Ok(picked)
+}
+
+/// 現在のフィルタ結果・選択状態・取得済みコミット情報を描画する
+fn render(
+    out: &mut impl Write,
+    query: &str,
+    targets: &[RepoTarget],
+    filtered: &[usize],
+    cursor_idx: usize,
+    selected: &[bool],
+    commit_cache: &HashMap<usize, Option<CommitInfo>>,
+) -> Result<()> {
+    queue!(out, cursor::MoveTo(0, 0), Clear(ClearType::All))?;
+    write!(out, "🔎 フィルタ: {}_\r\n", query)?;
+    write!(out, "↑/↓ 移動  Space 選択  Enter 決定（未選択ならハイライト行）  Esc/Ctrl-C 中止\r\n\r\n")?;
+
+    for (row, &idx) in filtered.iter().enumerate() {
+        let target = &targets[idx];
+        let marker = if selected[idx] { "[x]" } else { "[ ]" };
+        let pointer = if row == cursor_idx { ">" } else { " " };
+        let detail = match commit_cache.get(&idx) {
+            Some(Some(info)) => format!(" — {} by {} ({})", info.sha, info.login, info.date),
+            Some(None) => " — (履歴なし)".to_string(),
+            None => String::new(),
+        };
+        write!(out, "{} {} {}/{}{}\r\n", pointer, marker, target.owner, target.repo, detail)?;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+/// 選ばれたリポジトリを `dest_dir` 配下へ順に clone する（clone ごとにスピナー表示）
+fn clone_targets(targets: &[RepoTarget], clone_base_url: &str, dest_dir: &str) -> Result<()> {
+    std::fs::create_dir_all(dest_dir)
+        .with_context(|| format!("クローン先ディレクトリの作成に失敗: {}", dest_dir))?;
+
+    for target in targets {
+        let url = format!("{}/{}/{}.git", clone_base_url.trim_end_matches('/'), target.owner, target.repo);
+        let dest = format!("{}/{}", dest_dir, target.repo);
+
+        let spinner = ProgressBar::new_spinner();
+        spinner.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner} {msg}")
+                .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+        );
+        spinner.set_message(format!("cloning {}/{}", target.owner, target.repo));
+        spinner.enable_steady_tick(Duration::from_millis(100));
+
+        let status = Command::new("git")
+            .args(["clone", "--quiet", &url, &dest])
+            .status()
+            .with_context(|| format!("git clone の起動に失敗: {}", url))?;
+
+        if status.success() {
+            spinner.finish_with_message(format!("✅ {}/{}", target.owner, target.repo));
+        } else {
+            spinner.finish_with_message(format!(
+                "❌ {}/{} (exit={:?})",
+                target.owner, target.repo, status.code()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// `--interactive` のエントリポイント
+/// - `clone_base_url`: `owner/repo.git` の前に付ける URL (例: `https://ghe.example.com`)
+pub async fn run(
+    forge: &dyn Forge,
+    targets: Vec<RepoTarget>,
+    clone_base_url: &str,
+    dest_dir: &str,
+) -> Result<()> {
+    if targets.is_empty() {
+        println!("対象のリポジトリが見つかりませんでした");
+        return Ok(());
+    }
+
+    let chosen = pick_targets(forge, &targets).await?;
+    if chosen.is_empty() {
+        println!("クローン対象が選択されませんでした");
+        return Ok(());
+    }
+
+    println!("📦 {} 件のリポジトリを {} へ clone します", chosen.len(), dest_dir);
+    clone_targets(&chosen, clone_base_url, dest_dir)
+}
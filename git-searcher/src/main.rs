@@ -1,28 +1,58 @@
 /* main.rs
-    GitHub Enterprise Server (GHES) 上の全リポジトリから指定ファイルを含むリポジトリを検索し、
-    該当ファイルに対する最新コミットのユーザー名、SHA、コミット日時、リポジトリ URL を取得・表示するスクリプト
+    各種 Git フォージ (GHES / GitHub.com / Gitea) 上の全リポジトリから指定ファイルを
+    含むリポジトリを検索し、該当ファイルに対するコミット履歴を取得・表示するスクリプト
     実装の背景:
-    - GHES 環境では GraphQL スキーマが GitHub.com と異なり object() が使えないため
-      REST でファイル検索、GraphQL で defaultBranchRef.history(path:) を利用
+    - `FORGE_KIND` でバックエンドを切り替えられるよう、フォージ固有の URL 組み立てや
+      REST/GraphQL の呼び出しは `Forge` トレイトの実装側 (forge_ghes/forge_dotcom/forge_gitea)
+      に閉じ込め、main はトレイトオブジェクト越しに呼ぶだけにしている
     - 複数の Option<T> を安全にアンラップすることでパニックやエラーを回避
     - API 過負荷対策として各リポジトリ処理後にスリープを挿入
-*/ 
+*/
 
 use anyhow::{Context, Result};                      // エラー伝播を簡潔に扱うため
 use dotenv::dotenv;                                 // .env ファイルから環境変数をロード
-use graphql_client::{GraphQLQuery, Response};       // graphql_client derive 用
-use reqwest::Client;                                // HTTP リクエスト用
-use serde_json::Value;                              // REST レスポンス JSON パース用
-use std::{collections::BTreeSet, env};              // リポジトリセットと env 参照用
+use sqlx::sqlite::SqlitePool;                       // --since-last-run 用の SQLite プール
+use std::env;                                       // env 参照用
 use tokio::time::{sleep, Duration};                 // 非同期スリープ
 
+mod atom_feed;                                      // Atom フィード出力
+mod chunked_query;                                  // コネクション型クエリの汎用ページングランナー
+mod forge;                                          // Forge トレイト定義
+mod forge_ghes;                                     // GHES 向け Forge 実装
+#[cfg(feature = "dotcom")]
+mod forge_dotcom;                                   // GitHub.com 向け Forge 実装
+#[cfg(feature = "gitea")]
+mod forge_gitea;                                    // Gitea/Forgejo 向け Forge 実装
+mod interactive;                                     // --interactive 時のファジー picker + clone
 mod query;                                          // GraphQL クエリ定義を保持するモジュール
-use crate::query::FileBlame;                        // GraphQLQuery derive された構造体
-use crate::query::file_blame::{                     
-    Variables,                                      // クエリ変数型
-    ResponseData,                                   // レスポンスデータ型
-    FileBlameRepositoryDefaultBranchRefTarget,      // defaultBranchRef.target の enum
-};
+mod store;                                           // SQLite による前回実行状態の永続化
+
+use crate::forge::Forge;
+use crate::forge_ghes::GhesForge;
+
+/// `--output atom` で書き出す Atom フィードの出力先
+const ATOM_OUTPUT_PATH: &str = "git-searcher-feed.xml";
+
+/// `--interactive` で選んだリポジトリを clone する先のディレクトリ
+const CLONE_DEST_DIR: &str = "git-searcher-clones";
+
+/// `FORGE_KIND` で選べるバックエンドの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ForgeKind {
+    Ghes,
+    Dotcom,
+    Gitea,
+}
+
+/// 結果の出力形式
+/// - Text: 従来どおり絵文字付きで標準出力に都度表示
+/// - Atom: `ATOM_OUTPUT_PATH` に Atom フィードとして書き出す
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Atom,
+}
+
 ///--------------------------------------
 /// 設定値をまとめる構造体
 ///--------------------------------------
@@ -30,7 +60,11 @@ struct Config {
     ghe_url: String,
     token: String,
     filename: String,
-    graphql_url: String,
+    forge_kind: ForgeKind,
+    output: OutputFormat,
+    since_last_run: bool,
+    interactive: bool,
+    no_tty: bool,
 }
 
 /// 指定ファイルが見つかった (リポジトリ, パス) を表す
@@ -45,6 +79,7 @@ struct RepoTarget {
 #[derive(Debug, Clone)]
 struct CommitInfo {
     repo_full: String,
+    path: String,
     url: String,
     login: String,
     sha: String,
@@ -57,213 +92,102 @@ struct CommitInfo {
 fn load_config() -> Result<Config> {
     dotenv().ok();
 
-    // GHE_URL: GHES のベース URL
+    // GHE_URL: GHES/Gitea のベース URL (DotcomForge では使われない)
     let ghe_url = env::var("GHE_URL").context("環境変数 GHE_URL が設定されていません")?;
     // GITHUB_TOKEN: 認証用トークン
     let token   = env::var("GITHUB_TOKEN").context("環境変数 GITHUB_TOKEN が設定されていません")?;
-    // 実行時引数で検索対象のファイル名を取得
-    let filename = env::args()
-        .nth(1)
-        .context("Usage: cargo run -- <filename>")?;
-    // GraphQL エンドポイントの URL (GHES 固有)
-    let graphql_url = format!("{}/api/graphql", ghe_url.trim_end_matches('/'));
-
-    Ok(Config { ghe_url, token, filename, graphql_url })
-}
-
-///--------------------------------------
-/// REST: /search/code で filename マッチを全ページ走査
-/// - 戻り値は重複を排した RepoTarget のベクタ
-///--------------------------------------
-async fn search_repos_with_file(
-    rest: &Client,
-    cfg: &Config,
-) -> Result<Vec<RepoTarget>> {
-    let mut set: BTreeSet<(String, String)> = BTreeSet::new(); // (repo_full, path)
-
-    // GHES の search API は GitHub.com と同様に利用可能
-    let search_url = format!("{}/api/v3/search/code", cfg.ghe_url.trim_end_matches('/'));
-    let mut page = 1usize;
-
-    loop {
-        let resp = rest
-            .get(&search_url)
-            .bearer_auth(&cfg.token)
-            .query(&[
-                ("q", format!("filename:{}", cfg.filename)),
-                ("per_page", "100".to_string()),
-                ("page", page.to_string()),
-            ])
-            .send()
-            .await?
-            .error_for_status()
-            .with_context(|| format!("search/code(page={}) の呼び出しに失敗", page))?;
-
-        // JSON 文字列を serde_json::Value にデコード
-        let body: Value = resp.json().await
-            .context("search/code の JSON パースに失敗")?;
-
-        let items = body["items"].as_array().cloned().unwrap_or_default();
-        if items.is_empty() {
-            break; // ページ終端
-        }
 
-        for item in items {
-            if let (Some(repo_full), Some(path)) = (
-                item["repository"]["full_name"].as_str(),
-                item["path"].as_str(),
-            ) {
-                set.insert((repo_full.to_string(), path.to_string()));
+    // 実行時引数を走査してファイル名、--output <text|atom>、--since-last-run/--full を取り出す
+    let mut filename: Option<String> = None;
+    let mut output = OutputFormat::Text;
+    let mut since_last_run = false; // 既定はフルスキャン（従来どおり）
+    let mut interactive = false;    // 既定はバッチ実行（従来どおり）
+    let mut no_tty = false;         // --interactive があってもスクリプト実行時は無効化する逃げ道
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => {
+                let value = args.next().context("--output には text か atom を指定してください")?;
+                output = match value.as_str() {
+                    "atom" => OutputFormat::Atom,
+                    _ => OutputFormat::Text,
+                };
             }
+            "--since-last-run" => since_last_run = true,
+            "--full" => since_last_run = false,
+            "--interactive" => interactive = true,
+            "--no-tty" => no_tty = true,
+            other if filename.is_none() => filename = Some(other.to_string()),
+            _ => {} // 余分な位置引数は無視
         }
-
-        page += 1;
-        // ページまたぎの過負荷対策
-        sleep(Duration::from_millis(250)).await;
     }
+    let filename = filename.context(
+        "Usage: cargo run -- <filename> [--output text|atom] [--since-last-run|--full] [--interactive] [--no-tty]",
+    )?;
+
+    // FORGE_KIND: "ghes" (既定) / "dotcom" / "gitea" のどのバックエンドを使うか
+    let forge_kind = match env::var("FORGE_KIND").unwrap_or_else(|_| "ghes".to_string()).as_str() {
+        "dotcom" => ForgeKind::Dotcom,
+        "gitea" => ForgeKind::Gitea,
+        _ => ForgeKind::Ghes,
+    };
 
-    let targets = set.into_iter().map(|(repo_full, path)| {
-        let (owner, repo) = repo_full
-            .split_once('/')
-            .expect("Invalid repo format");
-        RepoTarget { owner: owner.to_string(), repo: repo.to_string(), path }
-    }).collect();
-
-    Ok(targets)
+    Ok(Config { ghe_url, token, filename, forge_kind, output, since_last_run, interactive, no_tty })
 }
 
 ///--------------------------------------
-/// REST: /repos/{owner}/{repo} で default_branch 確認（任意）
-/// - なくても GraphQL は動くことが多いが、健全性チェックとして保持
+/// `FORGE_KIND` に応じて `Forge` 実装を選ぶ
+/// - 対応する cargo feature が有効でないバックエンドを選ぶとエラーにする
 ///--------------------------------------
-async fn ensure_repo_info(
-    rest: &Client,
-    cfg: &Config,
-    target: &RepoTarget,
-) -> Result<()> {
-    let url = format!(
-        "{}/api/v3/repos/{}/{}",
-        cfg.ghe_url.trim_end_matches('/'),
-        target.owner,
-        target.repo
-    );
-
-    // 基本使わないが API レベルでのリポジトリ確認
-    let info: Value = rest
-        .get(&url)
-        .bearer_auth(&cfg.token)
-        .send()
-        .await?
-        .error_for_status()
-        .with_context(|| format!("GET {} に失敗", url))?
-        .json()
-        .await
-        .context("repo info JSON パースに失敗")?;
-
-    if info["default_branch"].is_null() {
-        // ここでは警告に留める（GraphQL で defaultBranchRef がなくても safe にハンドリング）
-        eprintln!("⚠️ default_branch が取得できません: {}/{}", target.owner, target.repo);
+fn build_forge(cfg: &Config) -> Result<Box<dyn Forge>> {
+    match cfg.forge_kind {
+        ForgeKind::Ghes => Ok(Box::new(GhesForge::new(&cfg.ghe_url, &cfg.token))),
+        ForgeKind::Dotcom => {
+            #[cfg(feature = "dotcom")]
+            {
+                Ok(Box::new(crate::forge_dotcom::DotcomForge::new(&cfg.token)))
+            }
+            #[cfg(not(feature = "dotcom"))]
+            {
+                anyhow::bail!("FORGE_KIND=dotcom を使うには `--features dotcom` でビルドしてください")
+            }
+        }
+        ForgeKind::Gitea => {
+            #[cfg(feature = "gitea")]
+            {
+                Ok(Box::new(crate::forge_gitea::GiteaForge::new(&cfg.ghe_url, &cfg.token)))
+            }
+            #[cfg(not(feature = "gitea"))]
+            {
+                anyhow::bail!("FORGE_KIND=gitea を使うには `--features gitea` でビルドしてください")
+            }
+        }
     }
-    Ok(())
 }
 
 ///--------------------------------------
-/// GraphQL: 指定 path の最新コミット 1 件を取得
-/// - 成功時は CommitInfo を返す
-/// - defaultBranchRef がない/履歴がない等は Ok(None)
+/// SQLite: `--since-last-run` 時に前回確認済みの sha より新しい分だけを取得する
+/// - `Forge::commits_since` が前回の sha に到達した時点でページ取得自体を打ち切るため、
+///   `latest_commit` で全履歴を取ってから手元でフィルタするより安上がりになる
+/// - `commits_since` は新しい順なので、`fresh` が空でなければ先頭が最新コミット。
+///   これをそのまま `file_commits` の更新に使い、`latest_commit_preview` の再取得を省く
+///   (差分がなければ `file_commits` も変わっていないので更新不要)
 ///--------------------------------------
-async fn fetch_latest_commit_for_path(
-    graphql: &Client,
-    cfg: &Config,
+async fn fetch_since_last_run(
+    forge: &dyn Forge,
+    pool: &SqlitePool,
+    repo_full: &str,
     target: &RepoTarget,
-) -> Result<Option<CommitInfo>> {
-    // GraphQL 変数
-    let variables = Variables {
-        owner: target.owner.clone(),
-        repo:  target.repo.clone(),
-        path:  target.path.clone(),
-    };
-
-    // 下記の処理でくGrapnQLのクエリに variables を渡しており、
-    // GraphQL内の下記の処理内のhistory フィールドには path 引数を渡せる仕様があります。
-    // これにより、指定したファイルに対するコミット履歴だけがフィルタされる。
-    // first: 1 にしているので、そのファイルを最後に更新したコミットが1件だけ返ってくる。
-    // 以降はその情報に対して、コミット日時やユーザーを取得していく
-    // 
-    // history(path: $path, first: 1) {
-
-    let req_body = FileBlame::build_query(variables);
-
-    let res = graphql
-        .post(&cfg.graphql_url)
-        .bearer_auth(&cfg.token)
-        .json(&req_body)
-        .send()
-        .await
-        .with_context(|| format!("GraphQL POST 失敗: {}/{}", target.owner, target.repo))?;
-
-    let response_body: Response<ResponseData> = res
-        .json()
-        .await
-        .context("GraphQL レスポンス JSON パースに失敗")?;
-
-    // repository が None のときは情報不足として None
-    let Some(repo_data) = response_body
-        .data
-        .as_ref()
-        .and_then(|d| d.repository.as_ref())
-    else {
-        eprintln!("⚠️ GraphQL repository null: {}/{}", target.owner, target.repo);
-        return Ok(None);
-    };
-
-    //  defaultBranchRef.target → Commit 取得
-    let Some(commit_target) = repo_data
-        .default_branch_ref
-        .as_ref()
-        .and_then(|r| r.target.as_ref())
-    else {
-        eprintln!("⚠️ defaultBranchRef.target なし: {}/{}", target.owner, target.repo);
-        return Ok(None);
-    };
-
-    // enum から Commit 以外は来ない想定（来たら None）
-    let commit = match commit_target {
-        FileBlameRepositoryDefaultBranchRefTarget::Commit(c) => c,
-    };
-
-    // history(path: $path, first: 1) の node を読む
-    // Commit.history.edges → 最新コミットノードを取得
-    let node = commit
-        .history
-        .as_ref()
-        .and_then(|h| h.edges.as_ref())
-        .and_then(|edges| edges.first())
-        .and_then(|edge_opt| edge_opt.as_ref())
-        .and_then(|edge| edge.node.as_ref());
-
-    let Some(node) = node else {
-        eprintln!("⚠️ history.edges.node なし: {}/{}", target.owner, target.repo);
-        return Ok(None);
-    };
+) -> Result<Vec<CommitInfo>> {
+    let last_sha = store::last_seen_sha(pool, repo_full, &target.path).await?;
 
-    // CommitNode からユーザー名・SHA・日付を取り出し
-    // login 優先、なければ author.name
-    let login = node.author
-        .as_ref()
-        .and_then(|a| a.user.as_ref())
-        .and_then(|u| u.login.as_ref())
-        .map(|s| s.to_string())
-        .or_else(|| node.author.as_ref().and_then(|a| a.name.clone()))
-        .unwrap_or_else(|| "unknown".to_string());
+    let fresh = forge.commits_since(target, last_sha.as_deref()).await?;
 
-    let sha  = node.abbreviated_oid.as_deref().unwrap_or("-").to_string();
-    let date = node.committed_date.as_deref().unwrap_or("-").to_string();
-
-    let repo_full = format!("{}/{}", target.owner, target.repo);
-    let url = format!("{}/{}/{}", cfg.ghe_url.trim_end_matches('/'), target.owner, target.repo);
+    if let Some(latest) = fresh.first() {
+        store::upsert(pool, repo_full, &target.path, &latest.sha, &latest.date).await?;
+    }
 
-    Ok(Some(CommitInfo { repo_full, url, login, sha, date }))
+    Ok(fresh)
 }
 
 ///--------------------------------------
@@ -281,27 +205,57 @@ fn print_commit(info: &CommitInfo) {
 ///--------------------------------------
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cfg = load_config()?;
+    // スキーマの introspection は `cargo run --bin fetch-schema` (独立バイナリ) で行う。
+    // `query.rs` の derive が `src/schema.json` をコンパイル時に要求するため、
+    // このバイナリのサブコマンドにはできない (本体がビルドできないと生成できず、
+    // 生成しないと本体がビルドできないという堂々巡りになる)
 
-    // クライアントは生成コストが高いので 1 度だけ
-    let rest    = Client::new();
-    let graphql = Client::new();
+    let cfg = load_config()?;
+    let forge = build_forge(&cfg)?;
 
     // 1) ファイルを含むリポジトリを検索（全ページ）
-    let targets = search_repos_with_file(&rest, &cfg).await?;
+    let targets = forge.search_files(&cfg.filename).await?;
     println!("🔍 `{}` を含むリポジトリ: {} 件", cfg.filename, targets.len());
 
-    // 2) 各リポジトリごとに GraphQL で最新コミットを取得
-    for target in targets {
-        // 健全性チェック（任意）
-        let _ = ensure_repo_info(&rest, &cfg, &target).await;
+    // --interactive なら fuzzy picker + clone に分岐し、バッチ表示はスキップする
+    // (--no-tty はスクリプト実行からの誤発火を防ぐ逃げ道)
+    // clone 先の URL は選択中の forge から取る (DotcomForge では GHE_URL を使わない)
+    if cfg.interactive && !cfg.no_tty {
+        let clone_base_url = forge.clone_base_url();
+        return interactive::run(forge.as_ref(), targets, &clone_base_url, CLONE_DEST_DIR).await;
+    }
 
-        match fetch_latest_commit_for_path(&graphql, &cfg, &target).await {
-            Ok(Some(info)) => print_commit(&info),
-            Ok(None) => {
-                // ファイルが defaultBranch になかった・履歴が空 など
+    // --since-last-run のときだけ SQLite ストアを開く（通常実行に副作用を持たせない）
+    let store_pool = if cfg.since_last_run {
+        Some(store::open(store::DB_PATH).await?)
+    } else {
+        None
+    };
+
+    // Atom 出力時は全件集めてからまとめてフィードに書き出す
+    let mut feed_entries: Vec<CommitInfo> = Vec::new();
+
+    // 2) 各リポジトリごとにコミット履歴を取得
+    for target in targets {
+        let repo_full = format!("{}/{}", target.owner, target.repo);
+        let history = match &store_pool {
+            Some(pool) => fetch_since_last_run(forge.as_ref(), pool, &repo_full, &target).await,
+            None => forge.latest_commit(&target).await,
+        };
+
+        match history {
+            Ok(history) if history.is_empty() => {
+                // ファイルが defaultBranch になかった・履歴が空・前回から変更なし など
                 println!("⚠️ 該当コミットが見つかりません: {}/{}", target.owner, target.repo);
             }
+            Ok(history) => match cfg.output {
+                OutputFormat::Text => {
+                    for info in &history {
+                        print_commit(info);
+                    }
+                }
+                OutputFormat::Atom => feed_entries.extend(history),
+            },
             Err(e) => {
                 eprintln!("❌ 取得失敗 {}/{}: {:?}", target.owner, target.repo, e);
             }
@@ -311,5 +265,11 @@ async fn main() -> Result<()> {
         sleep(Duration::from_secs(1)).await;
     }
 
+    if cfg.output == OutputFormat::Atom {
+        let feed = atom_feed::build_feed(&cfg.filename, &cfg.ghe_url, &feed_entries);
+        atom_feed::write_feed(&feed, ATOM_OUTPUT_PATH)?;
+        println!("📰 Atom フィードを書き出しました: {}", ATOM_OUTPUT_PATH);
+    }
+
     Ok(())
 }
@@ -1,9 +1,180 @@
 use graphql_client::GraphQLQuery;
 
+use crate::chunked_query::ChunkedQuery;
+
 #[derive(GraphQLQuery)]
 #[graphql(
-    schema_path = "src/dummy.graphql",
+    schema_path = "src/schema.json", // `cargo run --bin fetch-schema` が書き出す実機の introspection 結果
     query_path  = "src/query.graphql",
-    response_derives = "Debug"
+    response_derives = "Debug",
+    variables_derives = "Clone" // run_chunked/run_chunked_until がページ毎に変数を clone するため
 )]
 pub struct FileBlame;
+
+/// `FileBlame.history` のページから取り出す 1 コミット分の情報
+/// (リポジトリ情報は呼び出し側が `RepoTarget` から補う)
+#[derive(Debug, Clone)]
+pub struct CommitNode {
+    pub sha: String,
+    pub date: String,
+    pub login: String,
+}
+
+/// `history(path: $path, first: $batch, after: $cursor)` を `ChunkedQuery` に乗せる
+/// - これにより `first: 1` 決め打ちをやめ、ファイルの全コミット履歴を辿れるようにする
+impl ChunkedQuery for FileBlame {
+    type Item = CommitNode;
+
+    fn change_after(vars: &mut file_blame::Variables, after: Option<String>) {
+        vars.cursor = after;
+    }
+
+    fn set_batch(vars: &mut file_blame::Variables, n: i64) {
+        vars.batch = n;
+    }
+
+    fn process(
+        data: file_blame::ResponseData,
+    ) -> anyhow::Result<(Vec<CommitNode>, Option<String>)> {
+        let Some(repo) = data.repository else {
+            return Ok((Vec::new(), None));
+        };
+        let Some(target) = repo.default_branch_ref.and_then(|r| r.target) else {
+            return Ok((Vec::new(), None));
+        };
+        // defaultBranchRef.target は GitObject (Blob/Commit/Tag/Tree の union) だが、
+        // ブランチの参照先は常に Commit のはずなので、それ以外は履歴なし扱いにする
+        let file_blame::FileBlameRepositoryDefaultBranchRefTarget::Commit(commit) = target else {
+            return Ok((Vec::new(), None));
+        };
+        let history = commit.history;
+
+        let items = history
+            .edges
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .filter_map(|edge| edge.node)
+            .map(|node| {
+                // login 優先、なければ author.name (main.rs の従来ロジックを踏襲)
+                let login = node
+                    .author
+                    .as_ref()
+                    .and_then(|a| a.user.as_ref())
+                    .map(|u| u.login.clone())
+                    .or_else(|| node.author.as_ref().and_then(|a| a.name.clone()))
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                CommitNode {
+                    sha: node.abbreviated_oid,
+                    date: node.committed_date,
+                    login,
+                }
+            })
+            .collect();
+
+        let next_cursor = if history.page_info.has_next_page {
+            history.page_info.end_cursor
+        } else {
+            None
+        };
+
+        Ok((items, next_cursor))
+    }
+
+    fn item_sha(item: &CommitNode) -> &str {
+        &item.sha
+    }
+}
+
+/// `object(expression:)` で直接 `Commit` を取得できる GitHub.com 向けのクエリ
+/// - GHES 版 (`FileBlame`) は `object(expression:)` が使えないため
+///   `defaultBranchRef.target` 経由で Commit にたどり着く workaround を踏む
+/// - GitHub.com の schema ではこの `object(expression:)` を直接呼べるため、
+///   `expression: "HEAD"` を渡して defaultBranchRef のホップを避ける
+#[cfg(feature = "dotcom")]
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/schema.json", // `cargo run --bin fetch-schema` が書き出す実機の introspection 結果
+    query_path  = "src/file_blame_dotcom.graphql",
+    response_derives = "Debug",
+    variables_derives = "Clone" // run_chunked がページ毎に変数を clone するため
+)]
+pub struct FileBlameDotcom;
+
+#[cfg(feature = "dotcom")]
+impl ChunkedQuery for FileBlameDotcom {
+    type Item = CommitNode;
+
+    fn change_after(vars: &mut file_blame_dotcom::Variables, after: Option<String>) {
+        vars.cursor = after;
+    }
+
+    fn set_batch(vars: &mut file_blame_dotcom::Variables, n: i64) {
+        vars.batch = n;
+    }
+
+    fn process(
+        data: file_blame_dotcom::ResponseData,
+    ) -> anyhow::Result<(Vec<CommitNode>, Option<String>)> {
+        let Some(object) = data.repository.and_then(|repo| repo.object) else {
+            return Ok((Vec::new(), None));
+        };
+        // `object(expression:)` の戻り値は GitObject (Blob/Commit/Tag/Tree の union) だが、
+        // `expression: "HEAD"` なので常に Commit のはず。それ以外は履歴なし扱いにする
+        let file_blame_dotcom::FileBlameDotcomRepositoryObject::Commit(commit) = object else {
+            return Ok((Vec::new(), None));
+        };
+        let history = commit.history;
+
+        let items = history
+            .edges
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .filter_map(|edge| edge.node)
+            .map(|node| {
+                // login 優先、なければ author.name (main.rs の従来ロジックを踏襲)
+                let login = node
+                    .author
+                    .as_ref()
+                    .and_then(|a| a.user.as_ref())
+                    .map(|u| u.login.clone())
+                    .or_else(|| node.author.as_ref().and_then(|a| a.name.clone()))
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                CommitNode {
+                    sha: node.abbreviated_oid,
+                    date: node.committed_date,
+                    login,
+                }
+            })
+            .collect();
+
+        let next_cursor = if history.page_info.has_next_page {
+            history.page_info.end_cursor
+        } else {
+            None
+        };
+
+        Ok((items, next_cursor))
+    }
+
+    fn item_sha(item: &CommitNode) -> &str {
+        &item.sha
+    }
+}
+
+/// `search(type: REPOSITORY)` をカーソルでページングするためのクエリ
+/// - REST の `/search/code` は検索結果を 1000 件までしか返さないため、
+///   大規模な GHES インスタンスではリポジトリ数が多いと取りこぼしが発生する
+/// - こちらはリポジトリそのものをカーソルで全件列挙し、各リポジトリに対して
+///   個別にファイルの有無を問い合わせる方式で 1000 件の壁を回避する
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/schema.json", // `cargo run --bin fetch-schema` が書き出す実機の introspection 結果
+    query_path  = "src/repo_search.graphql",
+    response_derives = "Debug",
+    variables_derives = "Clone" // search_repos_with_file_graphql はページ毎に Variables を組み立て直すので clone はしていない
+)]
+pub struct RepoSearch;
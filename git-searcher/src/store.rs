@@ -0,0 +1,76 @@
+/* store.rs
+    `(repo_full, path)` ごとに最後に見た sha/committed_date を保持する SQLite ストア
+    実装の背景:
+    - これまでは毎回フルスキャンして全件表示するだけのステートレスな作りだったため、
+      「前回実行からどのファイルが変わったか」が分からなかった
+    - sqlx 経由で SQLite に最終確認状態を永続化し、`--since-last-run` 実行時は
+      差分 (sha が変わった分) だけを報告できるようにする
+*/
+
+use anyhow::{Context, Result};
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+use sqlx::Row;
+
+/// ストアファイルのデフォルトパス
+pub const DB_PATH: &str = "git-searcher.db";
+
+/// DB を開き、未作成ならテーブルを用意する
+pub async fn open(db_path: &str) -> Result<SqlitePool> {
+    let url = format!("sqlite://{}?mode=rwc", db_path);
+    let pool = SqlitePoolOptions::new()
+        .connect(&url)
+        .await
+        .with_context(|| format!("SQLite ストアのオープンに失敗: {}", db_path))?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS file_commits (
+            repo_full      TEXT NOT NULL,
+            path           TEXT NOT NULL,
+            sha            TEXT NOT NULL,
+            committed_date TEXT NOT NULL,
+            PRIMARY KEY (repo_full, path)
+        )",
+    )
+    .execute(&pool)
+    .await
+    .context("file_commits テーブルの作成に失敗")?;
+
+    Ok(pool)
+}
+
+/// `(repo_full, path)` の最後に見た sha を取得 (未記録なら None)
+pub async fn last_seen_sha(pool: &SqlitePool, repo_full: &str, path: &str) -> Result<Option<String>> {
+    let row = sqlx::query("SELECT sha FROM file_commits WHERE repo_full = ? AND path = ?")
+        .bind(repo_full)
+        .bind(path)
+        .fetch_optional(pool)
+        .await
+        .context("file_commits の参照に失敗")?;
+
+    Ok(row.map(|r| r.get::<String, _>("sha")))
+}
+
+/// `(repo_full, path)` の最新 sha/committed_date を記録 (なければ挿入)
+pub async fn upsert(
+    pool: &SqlitePool,
+    repo_full: &str,
+    path: &str,
+    sha: &str,
+    committed_date: &str,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO file_commits (repo_full, path, sha, committed_date)
+         VALUES (?, ?, ?, ?)
+         ON CONFLICT (repo_full, path)
+         DO UPDATE SET sha = excluded.sha, committed_date = excluded.committed_date",
+    )
+    .bind(repo_full)
+    .bind(path)
+    .bind(sha)
+    .bind(committed_date)
+    .execute(pool)
+    .await
+    .context("file_commits の更新に失敗")?;
+
+    Ok(())
+}